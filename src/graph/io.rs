@@ -0,0 +1,203 @@
+// human-readable interchange formats for hand-authored test graphs: a whitespace-separated
+// adjacency-matrix text block, and Graphviz DOT for visualizing results, as an alternative to the
+// verbose serde JSON node/edge lists the rest of the crate uses.
+use std::fmt::Debug;
+
+use super::{Cost, Graph};
+
+// errors from the adjacency-matrix format, which (unlike the node/edge lists the rest of the
+// crate uses) can't represent every graph: it needs one row per column, and at most one edge
+// between any ordered pair of nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixError {
+    // a row's length didn't match the number of rows
+    NotSquare,
+    // a node had more than one outgoing edge to the same target, so there's no single cell to
+    // serialize the weight into
+    ParallelEdges,
+    // a cell wasn't a valid integer
+    InvalidWeight,
+}
+
+// builds a graph from a whitespace-separated adjacency-matrix text block: row `r`, column `c`
+// holds either `0` (no edge) or an edge weight, creating one node per row/column and one edge per
+// nonzero entry. `node_state` and `edge_props` build the user-defined node/edge payloads from a
+// node index and a parsed weight respectively.
+pub fn from_adjacency_matrix<NodeState, EdgeProps>(
+    matrix: &str,
+    node_state: impl Fn(usize) -> NodeState,
+    edge_props: impl Fn(i64) -> EdgeProps,
+) -> Result<Graph<NodeState, EdgeProps>, MatrixError>
+where
+    NodeState: Debug,
+    EdgeProps: Debug + Cost,
+{
+    let rows: Vec<Vec<i64>> = matrix
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.split_whitespace()
+                .map(|weight| weight.parse().map_err(|_| MatrixError::InvalidWeight))
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+
+    if rows.iter().any(|row| row.len() != rows.len()) {
+        return Err(MatrixError::NotSquare);
+    }
+
+    let mut graph = Graph::new();
+    for i in 0..rows.len() {
+        graph.insert_node(node_state(i));
+    }
+    for (from, row) in rows.iter().enumerate() {
+        for (to, &weight) in row.iter().enumerate() {
+            if weight != 0 {
+                graph.insert_edge(from, to, edge_props(weight));
+            }
+        }
+    }
+    Ok(graph)
+}
+
+// serializes a graph back to the adjacency-matrix form read by `from_adjacency_matrix`
+pub fn to_adjacency_matrix<NodeState, EdgeProps>(
+    graph: &Graph<NodeState, EdgeProps>,
+) -> Result<String, MatrixError>
+where
+    NodeState: Debug,
+    EdgeProps: Debug + Cost,
+    <EdgeProps as Cost>::Type: std::fmt::Display,
+{
+    let n = graph.node_count();
+    let mut rows = Vec::with_capacity(n);
+    for from in 0..n {
+        let mut row = vec!["0".to_string(); n];
+        let mut seen = vec![false; n];
+        for &edge_id in graph.node(from).outgoing.iter() {
+            let to = graph.edge(edge_id).to;
+            if seen[to] {
+                return Err(MatrixError::ParallelEdges);
+            }
+            seen[to] = true;
+            row[to] = graph.props(edge_id).cost().to_string();
+        }
+        rows.push(row.join(" "));
+    }
+    Ok(rows.join("\n"))
+}
+
+// serializes a graph to Graphviz DOT, labelling nodes and edges with their `Debug` output and
+// edge cost respectively
+pub fn to_dot<NodeState, EdgeProps>(graph: &Graph<NodeState, EdgeProps>) -> String
+where
+    NodeState: Debug,
+    EdgeProps: Debug + Cost,
+    <EdgeProps as Cost>::Type: std::fmt::Display,
+{
+    to_dot_with(graph, |state| format!("{:?}", state), |props| props.cost().to_string())
+}
+
+// like `to_dot`, but with caller-supplied node/edge label formatters instead of `Debug` output
+pub fn to_dot_with<NodeState, EdgeProps>(
+    graph: &Graph<NodeState, EdgeProps>,
+    node_label: impl Fn(&NodeState) -> String,
+    edge_label: impl Fn(&EdgeProps) -> String,
+) -> String
+where
+    NodeState: Debug,
+    EdgeProps: Debug + Cost,
+{
+    let mut dot = String::from("digraph {\n");
+    for from in 0..graph.node_count() {
+        for &edge_id in graph.node(from).outgoing.iter() {
+            let edge = graph.edge(edge_id);
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                node_label(graph.state(edge.from)),
+                node_label(graph.state(edge.to)),
+                edge_label(graph.props(edge_id)),
+            ));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct State {
+        name: usize,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Props {
+        cost: i64,
+    }
+
+    impl Cost for Props {
+        type Type = i64;
+        fn cost(&self) -> Self::Type {
+            self.cost
+        }
+        fn zero_cost() -> Self::Type {
+            0
+        }
+    }
+
+    #[test]
+    fn round_trips_through_an_adjacency_matrix() {
+        let matrix = "0 1 2\n0 0 3\n0 0 0";
+
+        let graph: Graph<State, Props> =
+            from_adjacency_matrix(matrix, |i| State { name: i }, |weight| Props { cost: weight }).unwrap();
+
+        assert_eq!(graph.node_count(), 3);
+        let path = graph.best_path(0, &[2]).unwrap();
+        assert_eq!(graph.cost(&path), 2);
+
+        assert_eq!(to_adjacency_matrix(&graph).unwrap(), matrix);
+    }
+
+    #[test]
+    fn formats_dot() {
+        let graph: Graph<State, Props> =
+            from_adjacency_matrix("0 3\n0 0", |i| State { name: i }, |weight| Props { cost: weight }).unwrap();
+
+        let dot = to_dot_with(&graph, |state| state.name.to_string(), |props| props.cost.to_string());
+
+        assert_eq!(dot, "digraph {\n    \"0\" -> \"1\" [label=\"3\"];\n}\n");
+    }
+
+    #[test]
+    fn rejects_a_non_square_matrix() {
+        let result: Result<Graph<State, Props>, _> =
+            from_adjacency_matrix("0 1\n0 0 0", |i| State { name: i }, |weight| Props { cost: weight });
+
+        assert_eq!(result.err(), Some(MatrixError::NotSquare));
+    }
+
+    #[test]
+    fn rejects_a_non_integer_weight() {
+        let result: Result<Graph<State, Props>, _> =
+            from_adjacency_matrix("0 x\n0 0", |i| State { name: i }, |weight| Props { cost: weight });
+
+        assert_eq!(result.err(), Some(MatrixError::InvalidWeight));
+    }
+
+    #[test]
+    fn rejects_parallel_edges() {
+        let mut graph: Graph<State, Props> = Graph::new();
+        let a = graph.insert_node(State { name: 0 });
+        let b = graph.insert_node(State { name: 1 });
+        graph.insert_edge(a, b, Props { cost: 1 });
+        graph.insert_edge(a, b, Props { cost: 2 });
+
+        assert_eq!(to_adjacency_matrix(&graph).err(), Some(MatrixError::ParallelEdges));
+    }
+}
@@ -1,14 +1,18 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::Add;
 
 mod priority_queue;
+pub mod io;
 
-// immutable graph, nodes and edges can be added but not deleted
+// data-oriented graph with user-defined node states and edge props;
+// nodes and edges can be inserted but not deleted
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Graph<NodeState: Debug, EdgeProps: Debug> {
     nodes: Vec<Node>,
-    states: Vec<NodeState>,
     edges: Vec<Edge>,
+    states: Vec<NodeState>,
     props: Vec<EdgeProps>,
 }
 
@@ -27,14 +31,30 @@ pub struct Edge {
 }
 
 pub trait Cost {
-    fn cost(&self) -> CostType;
+    type Type: Debug + Copy + PartialOrd + PartialEq + Ord + Add<Output = Self::Type> + Sum;
+    fn cost(&self) -> Self::Type;
+    fn zero_cost() -> Self::Type;
+}
+
+// a lower-bound estimate of the remaining cost from a node to the target,
+// used by `a_star_path` to focus the search
+pub trait Heuristic<EdgeProps: Cost> {
+    fn estimate(&self, node: NodeId) -> <EdgeProps as Cost>::Type;
+}
+
+// propagates a full node state along an edge instead of just summing a plain edge weight, so the
+// path cost can depend on the accumulated state rather than being an additive sum of edge costs
+// (time-dependent delays, fuel/charge caps, state-dependent penalties). `advance` must be
+// cost-nondecreasing for `best_path_stateful`'s Dijkstra-style search to stay correct.
+pub trait Advance<NodeState, EdgeProps> {
+    fn advance(&self, edge_props: &EdgeProps) -> NodeState;
+    fn cost(&self) -> Option<f64>;
 }
 
 type NodeId = usize;
 type EdgeId = usize;
-type CostType = f64;
 
-impl<NodeState: Debug, EdgeProps: Debug + Cost> Graph<NodeState, EdgeProps> {
+impl<NodeState: Debug, EdgeProps: Debug> Graph<NodeState, EdgeProps> {
     pub fn new() -> Self {
         Graph {
             nodes: Vec::new(),
@@ -43,6 +63,9 @@ impl<NodeState: Debug, EdgeProps: Debug + Cost> Graph<NodeState, EdgeProps> {
             props: Vec::new(),
         }
     }
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
     pub fn node(&self, id: NodeId) -> &Node {
         &self.nodes[id]
     }
@@ -55,7 +78,6 @@ impl<NodeState: Debug, EdgeProps: Debug + Cost> Graph<NodeState, EdgeProps> {
     pub fn props(&self, id: EdgeId) -> &EdgeProps {
         &self.props[id]
     }
-
     pub fn insert_node(&mut self, state: NodeState) -> NodeId {
         let new_node_id = self.nodes.len();
         self.nodes.push(Node {
@@ -78,37 +100,247 @@ impl<NodeState: Debug, EdgeProps: Debug + Cost> Graph<NodeState, EdgeProps> {
         self.nodes[to].incoming.push(new_edge_id);
         new_edge_id
     }
-    pub fn cheapest_path(&self, source: NodeId, target: NodeId) -> Option<Vec<EdgeId>> {
-        if source == target {
+    // like `best_path`, but for objectives that aren't a plain sum of edge costs: each relaxation
+    // computes `to_state = from_state.advance(edge_props)` and compares `to_state.cost()` against
+    // the best cost recorded for `to` so far, instead of adding up `EdgeProps::cost()`. A pure
+    // query: it doesn't touch `self.states`, and returns the accumulated state at the cheapest
+    // target alongside the path, so the caller reads the cost off of it directly.
+    pub fn best_path_stateful(&self, source: NodeId, targets: &[NodeId]) -> Option<(Vec<EdgeId>, NodeState)>
+    where
+        NodeState: Advance<NodeState, EdgeProps> + Clone,
+    {
+        if targets.contains(&source) {
+            return Some((Vec::new(), self.states[source].clone()));
+        }
+        let mut best_state: Vec<Option<NodeState>> = vec![None; self.nodes.len()];
+        let mut best_incoming: Vec<Option<EdgeId>> = vec![None; self.nodes.len()];
+        let mut is_closed = vec![false; self.nodes.len()];
+        let mut queue = priority_queue::Heap::<Option<f64>>::new();
+        let source_state = self.states[source].clone();
+        queue.insert(source, source_state.cost());
+        best_state[source] = Some(source_state);
+        while !queue.is_empty() {
+            let (from, _) = queue.extract_min().unwrap();
+            if is_closed[from] {
+                continue;
+            }
+            is_closed[from] = true;
+            let from_state = best_state[from].clone().unwrap();
+            for &edge_id in self.nodes[from].outgoing.iter() {
+                let to = self.edges[edge_id].to;
+                if to == from || is_closed[to] {
+                    continue;
+                }
+                let to_state = from_state.advance(&self.props[edge_id]);
+                let to_cost = to_state.cost();
+                let is_cheaper = match &best_state[to] {
+                    Some(existing) => to_cost < existing.cost(),
+                    None => true,
+                };
+                if is_cheaper {
+                    best_incoming[to] = Some(edge_id);
+                    queue.insert(to, to_cost);
+                    best_state[to] = Some(to_state);
+                }
+            }
+        }
+        let target = targets
+            .iter()
+            .cloned()
+            .filter(|&target| best_state[target].is_some())
+            .min_by(|&a, &b| {
+                best_state[a].as_ref().unwrap().cost()
+                    .partial_cmp(&best_state[b].as_ref().unwrap().cost())
+                    .unwrap()
+            })?;
+        let mut node_id = target;
+        let mut path = Vec::new();
+        while node_id != source {
+            match best_incoming[node_id] {
+                Some(edge_id) => {
+                    path.push(edge_id);
+                    node_id = self.edges[edge_id].from;
+                }
+                None => unreachable!(),
+            }
+        }
+        path.reverse();
+        Some((path, best_state[target].clone().unwrap()))
+    }
+}
+
+impl<NodeState: Debug, EdgeProps: Debug + Cost> Graph<NodeState, EdgeProps> {
+    pub fn cost(&self, path: &[EdgeId]) -> <EdgeProps as Cost>::Type {
+        path.iter()
+            .cloned()
+            .map(|edge_id| self.props[edge_id].cost())
+            .sum()
+    }
+    // find the cheapest path to any of the targets
+    pub fn best_path(&self, source: NodeId, targets: &[NodeId]) -> Option<Vec<EdgeId>> {
+        if targets.contains(&source) {
             return Some(Vec::new());
         }
-        let mut best_incoming: Vec<Option<BestIncoming>> = vec![None; self.nodes.len()];
-        let mut queue = priority_queue::Heap::new();
-        let source_cost = 0.0;
-        queue.insert(source, source_cost);
+        // from the source, use breadth-first search to find the cheapest incoming edge for each node
+        let mut best_incoming = vec![None; self.nodes.len()];
+        let mut best_cost = vec![None; self.nodes.len()];
+        let mut is_closed = vec![false; self.nodes.len()];
+        let mut queue = priority_queue::Heap::<<EdgeProps as Cost>::Type>::new();
+        queue.insert(source, EdgeProps::zero_cost());
         while !queue.is_empty() {
             let (from, from_cost) = queue.extract_min().unwrap();
+            is_closed[from] = true;
             for &edge_id in self.nodes[from].outgoing.iter() {
                 let to = self.edges[edge_id].to;
+                if to == from || is_closed[to] {
+                    // skip loopy edges (they just increase cost) or edges that end at a closed node,
+                    // since we're using priority queue and thus a closed node already has the best cost and incoming
+                    continue;
+                }
                 let to_cost = from_cost + self.props[edge_id].cost();
-                match best_incoming[to] {
-                    Some(BestIncoming(_, cost)) if cost <= to_cost => {
-                        continue;
-                    }
-                    _ => {
-                        best_incoming[to] = Some(BestIncoming(edge_id, to_cost));
-                        queue.insert(to, to_cost);
+                if best_cost[to].is_none() || to_cost < best_cost[to].unwrap() {
+                    best_cost[to] = Some(to_cost);
+                    best_incoming[to] = Some(edge_id);
+                    queue.insert(to, to_cost);
+                    // if 'to' is already queued, this lowers its key in place rather than
+                    // adding a second entry for it
+                }
+            }
+        }
+        // then find the cheapest path walking back from the cheapest target via the cheapest incoming edges
+        let cheapest_target: Option<NodeId> = targets
+            .iter()
+            .cloned()
+            .filter(|&target| best_cost[target].is_some())
+            .min_by_key(|&target| best_cost[target].unwrap());
+        let mut node_id = cheapest_target?;
+        let mut path = Vec::new();
+        while node_id != source {
+            if let Some(edge_id) = best_incoming[node_id] {
+                path.push(edge_id);
+                node_id = self.edges[edge_id].from;
+            } else {
+                unreachable!();
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+    // like `best_path`, but orders the search frontier by `g(node) + heuristic.estimate(node)`
+    // instead of `g(node)` alone, so a good heuristic explores far fewer nodes than plain Dijkstra.
+    // `heuristic` must be consistent, i.e. estimate(u) <= cost(u, v) + estimate(v) for every edge
+    // u->v, and estimate(target) == 0; an inconsistent but merely admissible heuristic can still
+    // underestimate a closed node's true cost, in which case the `is_closed` skip below must be
+    // dropped to allow nodes to be reopened.
+    pub fn a_star_path<H: Heuristic<EdgeProps>>(
+        &self,
+        source: NodeId,
+        target: NodeId,
+        heuristic: &H,
+    ) -> Option<Vec<EdgeId>> {
+        if source == target {
+            return Some(Vec::new());
+        }
+        let mut best_incoming = vec![None; self.nodes.len()];
+        let mut g = vec![None; self.nodes.len()];
+        let mut is_closed = vec![false; self.nodes.len()];
+        let mut queue = priority_queue::Heap::<<EdgeProps as Cost>::Type>::new();
+        g[source] = Some(EdgeProps::zero_cost());
+        queue.insert(source, EdgeProps::zero_cost() + heuristic.estimate(source));
+        while !queue.is_empty() {
+            let (from, _) = queue.extract_min().unwrap();
+            if is_closed[from] {
+                continue;
+            }
+            is_closed[from] = true;
+            if from == target {
+                break;
+            }
+            let from_g = g[from].unwrap();
+            for &edge_id in self.nodes[from].outgoing.iter() {
+                let to = self.edges[edge_id].to;
+                if to == from || is_closed[to] {
+                    continue;
+                }
+                let to_g = from_g + self.props[edge_id].cost();
+                if g[to].is_none() || to_g < g[to].unwrap() {
+                    g[to] = Some(to_g);
+                    best_incoming[to] = Some(edge_id);
+                    queue.insert(to, to_g + heuristic.estimate(to));
+                }
+            }
+        }
+        g[target]?;
+        let mut node_id = target;
+        let mut path = Vec::new();
+        while node_id != source {
+            if let Some(edge_id) = best_incoming[node_id] {
+                path.push(edge_id);
+                node_id = self.edges[edge_id].from;
+            } else {
+                unreachable!();
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+    // like `best_path`, but tolerates negative edge costs: Dijkstra is still used as the fast
+    // path when every edge cost is non-negative, otherwise the search falls back to Bellman-Ford.
+    // returns `Err(NegativeCycle)` if a cycle reachable from `source` can make the cost along it
+    // arbitrarily low, since then no shortest path exists.
+    pub fn shortest_path_robust(
+        &self,
+        source: NodeId,
+        targets: &[NodeId],
+    ) -> Result<Option<Vec<EdgeId>>, NegativeCycle> {
+        let has_negative_cost = self.props.iter().any(|props| props.cost() < EdgeProps::zero_cost());
+        if !has_negative_cost {
+            return Ok(self.best_path(source, targets));
+        }
+        let mut dist = vec![None; self.nodes.len()];
+        let mut best_incoming = vec![None; self.nodes.len()];
+        dist[source] = Some(EdgeProps::zero_cost());
+        for _ in 0..self.nodes.len().saturating_sub(1) {
+            let mut relaxed = false;
+            for edge in self.edges.iter() {
+                if let Some(from_dist) = dist[edge.from] {
+                    let to_dist = from_dist + self.props[edge.id].cost();
+                    if dist[edge.to].is_none() || to_dist < dist[edge.to].unwrap() {
+                        dist[edge.to] = Some(to_dist);
+                        best_incoming[edge.to] = Some(edge.id);
+                        relaxed = true;
                     }
                 }
             }
+            if !relaxed {
+                break;
+            }
         }
-        if best_incoming[target].is_none() {
-            return None;
+        // one extra pass: if an edge still relaxes, its source is on (or reachable from) a
+        // negative cycle, so no shortest path exists
+        for edge in self.edges.iter() {
+            if let Some(from_dist) = dist[edge.from] {
+                let to_dist = from_dist + self.props[edge.id].cost();
+                if dist[edge.to].is_none() || to_dist < dist[edge.to].unwrap() {
+                    return Err(NegativeCycle);
+                }
+            }
         }
+        if targets.contains(&source) {
+            return Ok(Some(Vec::new()));
+        }
+        let cheapest_target: Option<NodeId> = targets
+            .iter()
+            .cloned()
+            .filter(|&target| dist[target].is_some())
+            .min_by_key(|&target| dist[target].unwrap());
+        let mut node_id = match cheapest_target {
+            Some(node_id) => node_id,
+            None => return Ok(None),
+        };
         let mut path = Vec::new();
-        let mut node_id = target;
         while node_id != source {
-            if let Some(BestIncoming(edge_id, _)) = best_incoming[node_id] {
+            if let Some(edge_id) = best_incoming[node_id] {
                 path.push(edge_id);
                 node_id = self.edges[edge_id].from;
             } else {
@@ -116,9 +348,376 @@ impl<NodeState: Debug, EdgeProps: Debug + Cost> Graph<NodeState, EdgeProps> {
             }
         }
         path.reverse();
+        Ok(Some(path))
+    }
+    // like `best_path`, but up to `k` edges along the route may have their cost waived to zero
+    // (e.g. toll waivers, coupon hops, free transfers). Implemented as Dijkstra over an expanded
+    // state space of `(node, used)` pairs, where `used` in `0..=k` counts how many free edges
+    // have been consumed so far; each outgoing edge generates a normal transition within the same
+    // `used` layer, and, while `used < k`, an extra discounted transition into the next layer.
+    pub fn best_path_with_discounts(
+        &self,
+        source: NodeId,
+        targets: &[NodeId],
+        k: usize,
+    ) -> Option<Vec<EdgeId>> {
+        let layers = k + 1;
+        let state = |node: NodeId, used: usize| node * layers + used;
+
+        if targets.contains(&source) {
+            return Some(Vec::new());
+        }
+        let mut best_incoming: Vec<Option<(EdgeId, bool)>> = vec![None; self.nodes.len() * layers];
+        let mut best_cost = vec![None; self.nodes.len() * layers];
+        let mut is_closed = vec![false; self.nodes.len() * layers];
+        let mut queue = priority_queue::Heap::<<EdgeProps as Cost>::Type>::new();
+        best_cost[state(source, 0)] = Some(EdgeProps::zero_cost());
+        queue.insert(state(source, 0), EdgeProps::zero_cost());
+        while !queue.is_empty() {
+            let (from_state, from_cost) = queue.extract_min().unwrap();
+            if is_closed[from_state] {
+                continue;
+            }
+            is_closed[from_state] = true;
+            let from = from_state / layers;
+            let used = from_state % layers;
+            for &edge_id in self.nodes[from].outgoing.iter() {
+                let to = self.edges[edge_id].to;
+                if to == from {
+                    continue;
+                }
+                let normal_cost = from_cost + self.props[edge_id].cost();
+                let normal_state = state(to, used);
+                if !is_closed[normal_state]
+                    && (best_cost[normal_state].is_none() || normal_cost < best_cost[normal_state].unwrap())
+                {
+                    best_cost[normal_state] = Some(normal_cost);
+                    best_incoming[normal_state] = Some((edge_id, false));
+                    queue.insert(normal_state, normal_cost);
+                }
+                if used < k {
+                    let discounted_cost = from_cost + EdgeProps::zero_cost();
+                    let discounted_state = state(to, used + 1);
+                    if !is_closed[discounted_state]
+                        && (best_cost[discounted_state].is_none()
+                            || discounted_cost < best_cost[discounted_state].unwrap())
+                    {
+                        best_cost[discounted_state] = Some(discounted_cost);
+                        best_incoming[discounted_state] = Some((edge_id, true));
+                        queue.insert(discounted_state, discounted_cost);
+                    }
+                }
+            }
+        }
+        let cheapest_target_state: Option<usize> = targets
+            .iter()
+            .flat_map(|&target| (0..layers).map(move |used| state(target, used)))
+            .filter(|&target_state| best_cost[target_state].is_some())
+            .min_by_key(|&target_state| best_cost[target_state].unwrap());
+        let mut node_state = cheapest_target_state?;
+        let source_state = state(source, 0);
+        let mut path = Vec::new();
+        while node_state != source_state {
+            match best_incoming[node_state] {
+                Some((edge_id, discounted)) => {
+                    path.push(edge_id);
+                    let used = node_state % layers - if discounted { 1 } else { 0 };
+                    node_state = state(self.edges[edge_id].from, used);
+                }
+                None => unreachable!(),
+            }
+        }
+        path.reverse();
         Some(path)
     }
+    // like `best_path`, but lazily yields nodes in nondecreasing cost order instead of computing
+    // the whole shortest-path tree up front, so callers can stop early (nearest of several
+    // targets, all nodes within a budget) without paying for the rest of the graph
+    pub fn shortest_path_iter(&self, source: NodeId) -> ShortestPathIter<'_, NodeState, EdgeProps> {
+        let mut queue = priority_queue::Heap::<<EdgeProps as Cost>::Type>::new();
+        queue.insert(source, EdgeProps::zero_cost());
+        let mut best_cost = vec![None; self.nodes.len()];
+        best_cost[source] = Some(EdgeProps::zero_cost());
+        ShortestPathIter {
+            graph: self,
+            queue,
+            best_cost,
+            is_closed: vec![false; self.nodes.len()],
+        }
+    }
+    // collects `shortest_path_iter(source)` up to (but not including) the first node whose cost
+    // exceeds `budget`
+    pub fn distances_within(
+        &self,
+        source: NodeId,
+        budget: <EdgeProps as Cost>::Type,
+    ) -> Vec<(NodeId, <EdgeProps as Cost>::Type)> {
+        let mut result = Vec::new();
+        for (node, cost) in self.shortest_path_iter(source) {
+            if cost > budget {
+                break;
+            }
+            result.push((node, cost));
+        }
+        result
+    }
 }
 
-#[derive(Debug, Clone)]
-struct BestIncoming(EdgeId, CostType);
+pub struct ShortestPathIter<'a, NodeState: Debug, EdgeProps: Debug + Cost> {
+    graph: &'a Graph<NodeState, EdgeProps>,
+    queue: priority_queue::Heap<<EdgeProps as Cost>::Type>,
+    best_cost: Vec<Option<<EdgeProps as Cost>::Type>>,
+    is_closed: Vec<bool>,
+}
+
+impl<'a, NodeState: Debug, EdgeProps: Debug + Cost> Iterator for ShortestPathIter<'a, NodeState, EdgeProps> {
+    type Item = (NodeId, <EdgeProps as Cost>::Type);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (from, from_cost) = self.queue.extract_min()?;
+            if self.is_closed[from] {
+                continue;
+            }
+            self.is_closed[from] = true;
+            for &edge_id in self.graph.nodes[from].outgoing.iter() {
+                let to = self.graph.edges[edge_id].to;
+                if to == from || self.is_closed[to] {
+                    continue;
+                }
+                let to_cost = from_cost + self.graph.props[edge_id].cost();
+                if self.best_cost[to].is_none() || to_cost < self.best_cost[to].unwrap() {
+                    self.best_cost[to] = Some(to_cost);
+                    self.queue.insert(to, to_cost);
+                }
+            }
+            return Some((from, from_cost));
+        }
+    }
+}
+
+// returned by `shortest_path_robust` when a cycle reachable from the source has negative total
+// cost, so the cost to reach nodes past it is unbounded below and no shortest path exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegativeCycle;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct State {
+        name: char,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Props {
+        cost: u8,
+    }
+
+    impl Cost for Props {
+        type Type = u8;
+        fn cost(&self) -> Self::Type {
+            self.cost
+        }
+        fn zero_cost() -> Self::Type {
+            0u8
+        }
+    }
+
+    fn graph_from_edges(edges: &[(char, char, u8)]) -> (Graph<State, Props>, impl Fn(char) -> usize) {
+        let mut graph: Graph<State, Props> = Graph::new();
+        let mut id = HashMap::new();
+        for &(from_name, to_name, cost) in edges.iter() {
+            id.entry(from_name).or_insert(graph.insert_node(State { name: from_name }));
+            id.entry(to_name).or_insert(graph.insert_node(State { name: to_name }));
+            graph.insert_edge(*id.get(&from_name).unwrap(), *id.get(&to_name).unwrap(), Props { cost });
+        }
+        (graph, move |name| *id.get(&name).unwrap())
+    }
+
+    #[test]
+    fn test() {
+        let (graph, node_id) = graph_from_edges(&[
+            ('a', 'b', 1),
+            ('b', 'd', 10),
+            ('a', 'c', 2),
+            ('c', 'b', 3),
+            ('c', 'd', 8),
+            ]);
+        let from = |edge_id| graph.state(graph.edge(edge_id).from).name;
+        let to   = |edge_id| graph.state(graph.edge(edge_id).to).name;
+
+        assert_eq!(node_id('a'), 0);
+        let path = graph.best_path(node_id('a'), &[node_id('d')]).unwrap();
+        assert_eq!(path.len(), 2);
+        assert_eq!(from(path[0]), 'a');
+        assert_eq!(to(path[0]), 'c');
+        assert_eq!(from(path[1]), 'c');
+        assert_eq!(to(path[1]), 'd');
+        assert_eq!(graph.cost(&path), 10);
+    }
+
+    struct ZeroHeuristic;
+
+    impl Heuristic<Props> for ZeroHeuristic {
+        fn estimate(&self, _node: NodeId) -> u8 {
+            0
+        }
+    }
+
+    #[test]
+    fn a_star_matches_best_path() {
+        let (graph, node_id) = graph_from_edges(&[
+            ('a', 'b', 1),
+            ('b', 'd', 10),
+            ('a', 'c', 2),
+            ('c', 'b', 3),
+            ('c', 'd', 8),
+            ]);
+
+        let path = graph.a_star_path(node_id('a'), node_id('d'), &ZeroHeuristic).unwrap();
+
+        assert_eq!(path, graph.best_path(node_id('a'), &[node_id('d')]).unwrap());
+        assert_eq!(graph.cost(&path), 10);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SignedProps {
+        cost: i32,
+    }
+
+    impl Cost for SignedProps {
+        type Type = i32;
+        fn cost(&self) -> Self::Type {
+            self.cost
+        }
+        fn zero_cost() -> Self::Type {
+            0
+        }
+    }
+
+    #[test]
+    fn shortest_path_robust_with_negative_edge() {
+        let mut graph: Graph<State, SignedProps> = Graph::new();
+        let a = graph.insert_node(State { name: 'a' });
+        let b = graph.insert_node(State { name: 'b' });
+        let c = graph.insert_node(State { name: 'c' });
+        let ab = graph.insert_edge(a, b, SignedProps { cost: 4 });
+        let bc = graph.insert_edge(b, c, SignedProps { cost: -2 });
+        graph.insert_edge(a, c, SignedProps { cost: 5 });
+
+        let path = graph.shortest_path_robust(a, &[c]).unwrap().unwrap();
+
+        assert_eq!(path, [ab, bc]);
+        assert_eq!(graph.cost(&path), 2);
+    }
+
+    #[test]
+    fn shortest_path_robust_detects_negative_cycle() {
+        let mut graph: Graph<State, SignedProps> = Graph::new();
+        let a = graph.insert_node(State { name: 'a' });
+        let b = graph.insert_node(State { name: 'b' });
+        graph.insert_edge(a, b, SignedProps { cost: -1 });
+        graph.insert_edge(b, a, SignedProps { cost: -1 });
+
+        assert_eq!(graph.shortest_path_robust(a, &[b]), Err(NegativeCycle));
+    }
+
+    #[test]
+    fn best_path_with_discounts_waives_costliest_edge() {
+        let mut graph: Graph<State, Props> = Graph::new();
+        let a = graph.insert_node(State { name: 'a' });
+        let b = graph.insert_node(State { name: 'b' });
+        let c = graph.insert_node(State { name: 'c' });
+        let d = graph.insert_node(State { name: 'd' });
+        graph.insert_edge(a, b, Props { cost: 10 });
+        graph.insert_edge(b, c, Props { cost: 10 });
+        let ad = graph.insert_edge(a, d, Props { cost: 100 });
+        let dc = graph.insert_edge(d, c, Props { cost: 1 });
+
+        // with no free edges, a-b-c (cost 20) beats a-d-c (cost 101)
+        let path = graph.best_path_with_discounts(a, &[c], 0).unwrap();
+        assert_eq!(path.len(), 2);
+        assert_eq!(graph.cost(&path), 20);
+
+        // with one free edge to spend on the costliest hop, a-d-c wins since a-d is waived
+        let path = graph.best_path_with_discounts(a, &[c], 1).unwrap();
+        assert_eq!(path, [ad, dc]);
+    }
+
+    #[test]
+    fn shortest_path_iter_yields_nondecreasing_costs() {
+        let (graph, node_id) = graph_from_edges(&[
+            ('a', 'b', 1),
+            ('b', 'd', 10),
+            ('a', 'c', 2),
+            ('c', 'b', 3),
+            ('c', 'd', 8),
+            ]);
+
+        let names_in_order: Vec<char> = graph
+            .shortest_path_iter(node_id('a'))
+            .map(|(node, _)| graph.state(node).name)
+            .collect();
+
+        assert_eq!(names_in_order, ['a', 'b', 'c', 'd']);
+    }
+
+    #[test]
+    fn distances_within_stops_at_the_budget() {
+        let (graph, node_id) = graph_from_edges(&[
+            ('a', 'b', 1),
+            ('b', 'd', 10),
+            ('a', 'c', 2),
+            ('c', 'b', 3),
+            ('c', 'd', 8),
+            ]);
+
+        let within_budget: Vec<char> = graph
+            .distances_within(node_id('a'), 4)
+            .into_iter()
+            .map(|(node, _)| graph.state(node).name)
+            .collect();
+
+        assert_eq!(within_budget, ['a', 'b', 'c']);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct StatefulState {
+        name: char,
+        cost: Option<f64>,
+    }
+
+    impl Advance<StatefulState, Props> for StatefulState {
+        fn advance(&self, edge_props: &Props) -> StatefulState {
+            StatefulState {
+                name: self.name,
+                cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+            }
+        }
+        fn cost(&self) -> Option<f64> {
+            self.cost
+        }
+    }
+
+    #[test]
+    fn best_path_stateful_returns_accumulated_state() {
+        let mut graph: Graph<StatefulState, Props> = Graph::new();
+        let a = graph.insert_node(StatefulState { name: 'a', cost: None });
+        let b = graph.insert_node(StatefulState { name: 'b', cost: None });
+        let c = graph.insert_node(StatefulState { name: 'c', cost: None });
+        let d = graph.insert_node(StatefulState { name: 'd', cost: None });
+
+        graph.insert_edge(a, b, Props { cost: 1 });
+        graph.insert_edge(b, c, Props { cost: 90 });
+        let ad = graph.insert_edge(a, d, Props { cost: 10 });
+        let dc = graph.insert_edge(d, c, Props { cost: 20 });
+
+        let (path, final_state) = graph.best_path_stateful(a, &[c]).unwrap();
+
+        assert_eq!(path, [ad, dc]);
+        assert_eq!(final_state.cost, Some(30.0));
+    }
+}
@@ -0,0 +1,129 @@
+// d-ary min-heap keyed by cost (arity `D`, default 4), with an auxiliary `position` index so a
+// second `insert` for an already-queued node performs an in-place decrease-key instead of pushing
+// a duplicate entry. Children of index `i` live at `D*i+1 ..= D*i+D`, the parent at `(i-1)/D`.
+pub struct Heap<Cost: PartialOrd + Copy, const D: usize = 4> {
+    entries: Vec<(usize, Cost)>,
+    position: Vec<Option<usize>>,
+}
+
+impl<Cost: PartialOrd + Copy, const D: usize> Heap<Cost, D> {
+    pub fn new() -> Self {
+        Heap {
+            entries: Vec::new(),
+            position: Vec::new(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    // inserts `node_id` with the given cost, or, if it's already queued, decreases its key to the
+    // given cost; callers are expected to only ever call this with a cost cheaper than any prior
+    // one for the same node
+    pub fn insert(&mut self, node_id: usize, cost: Cost) {
+        if node_id >= self.position.len() {
+            self.position.resize(node_id + 1, None);
+        }
+        match self.position[node_id] {
+            Some(index) => {
+                if cost < self.entries[index].1 {
+                    self.entries[index].1 = cost;
+                    self.sift_up(index);
+                }
+            }
+            None => {
+                let index = self.entries.len();
+                self.entries.push((node_id, cost));
+                self.position[node_id] = Some(index);
+                self.sift_up(index);
+            }
+        }
+    }
+    pub fn extract_min(&mut self) -> Option<(usize, Cost)> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let last = self.entries.len() - 1;
+        self.swap(0, last);
+        let (node_id, cost) = self.entries.pop().unwrap();
+        self.position[node_id] = None;
+        if !self.entries.is_empty() {
+            self.sift_down(0);
+        }
+        Some((node_id, cost))
+    }
+    fn swap(&mut self, i: usize, j: usize) {
+        self.entries.swap(i, j);
+        self.position[self.entries[i].0] = Some(i);
+        self.position[self.entries[j].0] = Some(j);
+    }
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / D;
+            if self.entries[index].1 < self.entries[parent].1 {
+                self.swap(index, parent);
+                index = parent;
+            } else {
+                break;
+            }
+        }
+    }
+    fn sift_down(&mut self, mut index: usize) {
+        let len = self.entries.len();
+        loop {
+            let mut smallest = index;
+            for child in (D * index + 1)..=(D * index + D) {
+                if child < len && self.entries[child].1 < self.entries[smallest].1 {
+                    smallest = child;
+                }
+            }
+            if smallest == index {
+                break;
+            }
+            self.swap(index, smallest);
+            index = smallest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_in_increasing_cost_order() {
+        let mut heap = Heap::<u32>::new();
+        heap.insert(0, 5);
+        heap.insert(1, 1);
+        heap.insert(2, 3);
+        heap.insert(3, 2);
+        heap.insert(4, 4);
+
+        let order: Vec<usize> = std::iter::from_fn(|| heap.extract_min().map(|(node, _)| node)).collect();
+
+        assert_eq!(order, [1, 3, 2, 4, 0]);
+    }
+
+    #[test]
+    fn decrease_key_replaces_the_queued_entry() {
+        let mut heap = Heap::<u32>::new();
+        heap.insert(0, 10);
+        heap.insert(1, 5);
+        heap.insert(0, 1); // decrease-key, not a duplicate entry
+
+        assert_eq!(heap.extract_min(), Some((0, 1)));
+        assert_eq!(heap.extract_min(), Some((1, 5)));
+        assert_eq!(heap.extract_min(), None);
+    }
+
+    #[test]
+    fn honours_a_custom_arity() {
+        let mut heap = Heap::<u32, 2>::new();
+        for (node, cost) in [(0, 9), (1, 3), (2, 7), (3, 1), (4, 5)] {
+            heap.insert(node, cost);
+        }
+
+        let order: Vec<usize> = std::iter::from_fn(|| heap.extract_min().map(|(node, _)| node)).collect();
+
+        assert_eq!(order, [3, 1, 4, 2, 0]);
+    }
+}
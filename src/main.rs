@@ -1,6 +1,4 @@
-mod dijkstra;
-
-use dijkstra::Graph;
+use dijkstra::graph::Graph;
 use serde::{Deserialize, Serialize};
 
 fn main() -> Result<(), serde_json::error::Error> {
@@ -13,9 +11,9 @@ fn main() -> Result<(), serde_json::error::Error> {
     println!("{:?}", graph.state(25));
     println!("{:?}", graph.edge(94));
     println!("{:?}", graph.props(94));
-    if let Some(path) = graph.cheapest_path(0, &[23, 24, 25]) {
+    if let Some(path) = graph.best_path(0, &[23, 24, 25]) {
         println!("{:?}", path);
-        //println!("{}", graph.cost(&path));
+        println!("{:?}", graph.cost(&path));
     }
     Ok(())
 }
@@ -30,7 +28,7 @@ pub fn random_sample() -> Graph<State, Props> {
     for _ in 0..100 {
         let from = (rand::random::<u8>() / 10) as usize;
         let to = (rand::random::<u8>() / 10) as usize;
-        let cost = rand::random::<f64>();
+        let cost = rand::random::<u8>() as u32;
         graph.insert_edge(from, to, Props { cost });
     }
     graph
@@ -43,15 +41,15 @@ pub struct State {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Props {
-    cost: f64,
+    cost: u32,
 }
 
-impl dijkstra::Cost for Props {
-    type Type = f64;
+impl dijkstra::graph::Cost for Props {
+    type Type = u32;
     fn cost(&self) -> Self::Type {
         self.cost
     }
-    fn zero() -> Self::Type {
-        0.0
+    fn zero_cost() -> Self::Type {
+        0
     }
 }
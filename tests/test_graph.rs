@@ -61,10 +61,10 @@ fn best_path() {
     graph.insert_edge(d, b, Props { cost: 1 });
 
     // three paths are possible from a to c: ab-bc, ad-db-bc, and ad-dc
-    let path = graph.best_path(a, &[c]).unwrap();
+    let (path, final_state) = graph.best_path_stateful(a, &[c]).unwrap();
 
     assert_eq!(path, [ad, dc]);
-    assert_eq!(graph.state(c).cost, Some(30.0));
+    assert_eq!(final_state.cost, Some(30.0));
 }
 
 #[test]
@@ -77,10 +77,10 @@ fn fork() {
     graph.insert_edge(a, b, Props { cost: 2 });
     let ac = graph.insert_edge(a, c, Props { cost: 1 });
 
-    let path = graph.best_path(a, &[b, c]).unwrap();
+    let (path, final_state) = graph.best_path_stateful(a, &[b, c]).unwrap();
 
     assert_eq!(path, [ac]);
-    assert_eq!(graph.state(c).cost, Some(1.0));
+    assert_eq!(final_state.cost, Some(1.0));
 }
 
 #[test]
@@ -93,10 +93,10 @@ fn chain() {
     let ab = graph.insert_edge(a, b, Props { cost: 2 });
     graph.insert_edge(b, c, Props { cost: 1 });
 
-    let path = graph.best_path(a, &[b, c]).unwrap();
+    let (path, final_state) = graph.best_path_stateful(a, &[b, c]).unwrap();
 
     assert_eq!(path, [ab]);
-    assert_eq!(graph.state(b).cost, Some(2.0));
+    assert_eq!(final_state.cost, Some(2.0));
 }
 
 #[test]
@@ -113,10 +113,10 @@ fn multi_edge() {
     assert_ne!(u, w);
     assert_ne!(v, w);
 
-    let path = graph.best_path(a, &[b]).unwrap();
+    let (path, final_state) = graph.best_path_stateful(a, &[b]).unwrap();
 
     assert_eq!(path, [w]);
-    assert_eq!(graph.state(b).cost, Some(1.0));
+    assert_eq!(final_state.cost, Some(1.0));
 }
 
 #[test]
@@ -130,10 +130,10 @@ fn loopy_edge() {
 
     assert_ne!(u, v);
 
-    let path = graph.best_path(a, &[b]).unwrap();
+    let (path, final_state) = graph.best_path_stateful(a, &[b]).unwrap();
 
     assert_eq!(path, [v]);
-    assert_eq!(graph.state(b).cost, Some(2.0));
+    assert_eq!(final_state.cost, Some(2.0));
 }
 
 #[test]
@@ -142,7 +142,7 @@ fn disconnected() {
     let a = graph.insert_node(State { name: 'a', cost: None });
     let b = graph.insert_node(State { name: 'b', cost: None });
 
-    let path = graph.best_path(a, &[b]);
+    let path = graph.best_path_stateful(a, &[b]);
     assert!(path.is_none());
 }
 
@@ -164,9 +164,6 @@ impl Advance<State, Props> for State {
             cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
         }
     }
-    fn update(&mut self, node_state: State) {
-        self.cost = node_state.cost;
-    }
     fn cost(&self) -> Option<f64> {
         self.cost
     }